@@ -0,0 +1,91 @@
+//! Waiter bookkeeping for the blocking push/pop API.
+//!
+//! Kept separate from the queue backends' own head/tail state so that a `close()` racing with a
+//! producer or consumer that is mid-park is still observed: waiters register themselves *before*
+//! parking, and re-check the queue's state after registering, so a wakeup can never be missed between the two.
+
+#[cfg(feature = "blocking")]
+use std::sync::Mutex;
+#[cfg(feature = "blocking")]
+use std::thread::{self, Thread};
+
+#[cfg(feature = "blocking")]
+pub(crate) struct Waiters {
+    producers: Mutex<Vec<Thread>>,
+    consumers: Mutex<Vec<Thread>>,
+}
+
+#[cfg(feature = "blocking")]
+impl Waiters {
+    pub(crate) fn new() -> Waiters {
+        Waiters {
+            producers: Mutex::new(Vec::new()),
+            consumers: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Registers the current thread as a parked producer, unless it is registered already.
+    ///
+    /// Callers are expected to call this again after every failed retry (a wakeup doesn't
+    /// guarantee *this* thread wins the race for the freed slot), so registration is idempotent
+    /// per thread instead of appending a fresh entry every time: that would let one thread's
+    /// backlog of stale entries starve distinct waiters that registered earlier.
+    pub(crate) fn register_producer(&self) {
+        let mut producers = self.producers.lock().unwrap();
+        let current = thread::current();
+        if !producers.iter().any(|t| t.id() == current.id()) {
+            producers.push(current);
+        }
+    }
+
+    /// Registers the current thread as a parked consumer, unless it is registered already.
+    ///
+    /// See [`register_producer`](Self::register_producer) for why this is idempotent.
+    pub(crate) fn register_consumer(&self) {
+        let mut consumers = self.consumers.lock().unwrap();
+        let current = thread::current();
+        if !consumers.iter().any(|t| t.id() == current.id()) {
+            consumers.push(current);
+        }
+    }
+
+    /// Wakes every parked producer and consumer, e.g. because the queue was closed.
+    pub(crate) fn wake_all(&self) {
+        for t in self.producers.lock().unwrap().drain(..) {
+            t.unpark();
+        }
+        for t in self.consumers.lock().unwrap().drain(..) {
+            t.unpark();
+        }
+    }
+
+    /// Wakes one parked consumer, e.g. because an item was just pushed.
+    pub(crate) fn wake_one_consumer(&self) {
+        if let Some(t) = self.consumers.lock().unwrap().pop() {
+            t.unpark();
+        }
+    }
+
+    /// Wakes one parked producer, e.g. because an item was just popped.
+    pub(crate) fn wake_one_producer(&self) {
+        if let Some(t) = self.producers.lock().unwrap().pop() {
+            t.unpark();
+        }
+    }
+}
+
+#[cfg(not(feature = "blocking"))]
+pub(crate) struct Waiters;
+
+#[cfg(not(feature = "blocking"))]
+impl Waiters {
+    pub(crate) fn new() -> Waiters {
+        Waiters
+    }
+
+    pub(crate) fn wake_all(&self) {}
+
+    pub(crate) fn wake_one_consumer(&self) {}
+
+    pub(crate) fn wake_one_producer(&self) {}
+}
@@ -0,0 +1,195 @@
+use std::cell::UnsafeCell;
+use std::mem::MaybeUninit;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::ring::{Positions, CLOSED};
+use crate::{PopError, PushError};
+
+/// A slot in a bounded queue.
+struct Slot<T> {
+    /// The current stamp.
+    ///
+    /// If the stamp equals the tail, this slot is empty and ready for a push. If the stamp equals
+    /// the head plus one, this slot is full and ready for a pop.
+    stamp: AtomicUsize,
+
+    /// The value in this slot.
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+/// A bounded queue, implemented as a lock-free array-based MPMC ring buffer.
+///
+/// This is Dmitry Vyukov's bounded MPMC queue: each slot carries its own sequence number (the
+/// `stamp`), so producers and consumers only ever need a single CAS on the shared `head`/`tail`
+/// counter rather than a lock around the whole buffer. The head/tail position arithmetic lives in
+/// [`crate::ring`], shared with [`crate::recycle::Recycled`].
+pub(crate) struct Bounded<T> {
+    /// Head/tail position bookkeeping.
+    positions: Positions,
+
+    /// The buffer holding slots.
+    buffer: Box<[Slot<T>]>,
+}
+
+unsafe impl<T: Send> Send for Bounded<T> {}
+unsafe impl<T: Send> Sync for Bounded<T> {}
+
+impl<T> Bounded<T> {
+    /// Creates a new bounded queue with the given capacity.
+    pub(crate) fn new(cap: usize) -> Bounded<T> {
+        let positions = Positions::new(cap);
+
+        let buffer = (0..cap)
+            .map(|i| Slot {
+                stamp: AtomicUsize::new(i),
+                value: UnsafeCell::new(MaybeUninit::uninit()),
+            })
+            .collect();
+
+        Bounded { positions, buffer }
+    }
+
+    /// Attempts to push an item into the queue.
+    pub(crate) fn push(&self, value: T) -> Result<(), PushError<T>> {
+        let mut tail = self.positions.tail.load(Ordering::Relaxed);
+
+        loop {
+            if tail & CLOSED != 0 {
+                return Err(PushError::Closed(value));
+            }
+
+            let index = self.positions.index(tail);
+            let slot = &self.buffer[index];
+            let stamp = slot.stamp.load(Ordering::Acquire);
+
+            let diff = stamp as isize - tail as isize;
+
+            if diff == 0 {
+                let new_tail = self.positions.next(tail);
+
+                match self.positions.tail.compare_exchange_weak(
+                    tail,
+                    new_tail,
+                    Ordering::SeqCst,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => {
+                        unsafe {
+                            slot.value.get().write(MaybeUninit::new(value));
+                        }
+                        slot.stamp.store(tail + 1, Ordering::Release);
+                        return Ok(());
+                    }
+                    Err(t) => tail = t,
+                }
+            } else if diff < 0 {
+                return Err(PushError::Full(value));
+            } else {
+                tail = self.positions.tail.load(Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Attempts to pop an item from the queue.
+    pub(crate) fn pop(&self) -> Result<T, PopError> {
+        let mut head = self.positions.head.load(Ordering::Relaxed);
+
+        loop {
+            let index = self.positions.index(head);
+            let slot = &self.buffer[index];
+            let stamp = slot.stamp.load(Ordering::Acquire);
+
+            let diff = stamp as isize - (head + 1) as isize;
+
+            if diff == 0 {
+                let new_head = self.positions.next(head);
+
+                match self.positions.head.compare_exchange_weak(
+                    head,
+                    new_head,
+                    Ordering::SeqCst,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => {
+                        let value = unsafe { slot.value.get().read().assume_init() };
+                        slot.stamp.store(head + self.positions.one_lap, Ordering::Release);
+                        return Ok(value);
+                    }
+                    Err(h) => head = h,
+                }
+            } else if diff < 0 {
+                let tail = self.positions.tail.load(Ordering::SeqCst);
+
+                if tail & !CLOSED == head {
+                    return Err(if tail & CLOSED != 0 {
+                        PopError::Closed
+                    } else {
+                        PopError::Empty
+                    });
+                }
+
+                head = self.positions.head.load(Ordering::Relaxed);
+            } else {
+                head = self.positions.head.load(Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Returns `true` if the queue is empty.
+    pub(crate) fn is_empty(&self) -> bool {
+        let head = self.positions.head.load(Ordering::SeqCst);
+        let tail = self.positions.tail.load(Ordering::SeqCst);
+        head == tail & !CLOSED
+    }
+
+    /// Returns `true` if the queue is full.
+    pub(crate) fn is_full(&self) -> bool {
+        let head = self.positions.head.load(Ordering::SeqCst);
+        let tail = self.positions.tail.load(Ordering::SeqCst) & !CLOSED;
+        self.positions.count(tail) - self.positions.count(head) == self.positions.cap
+    }
+
+    /// Returns the number of items in the queue.
+    pub(crate) fn len(&self) -> usize {
+        loop {
+            let tail = self.positions.tail.load(Ordering::SeqCst);
+            let head = self.positions.head.load(Ordering::SeqCst);
+
+            if self.positions.tail.load(Ordering::SeqCst) == tail {
+                return self.positions.count(tail & !CLOSED) - self.positions.count(head);
+            }
+        }
+    }
+
+    /// Returns the capacity of the queue.
+    pub(crate) fn capacity(&self) -> usize {
+        self.positions.cap
+    }
+
+    /// Closes the queue, returning `true` if this call closed it.
+    pub(crate) fn close(&self) -> bool {
+        let tail = self.positions.tail.fetch_or(CLOSED, Ordering::SeqCst);
+        tail & CLOSED == 0
+    }
+
+    /// Returns `true` if the queue is closed.
+    pub(crate) fn is_closed(&self) -> bool {
+        self.positions.tail.load(Ordering::SeqCst) & CLOSED != 0
+    }
+}
+
+impl<T> Drop for Bounded<T> {
+    fn drop(&mut self) {
+        let head = *self.positions.head.get_mut();
+        let tail = *self.positions.tail.get_mut() & !CLOSED;
+        let mut pos = head;
+
+        while pos != tail {
+            let slot = &mut self.buffer[self.positions.index(pos)];
+            unsafe {
+                slot.value.get_mut().assume_init_drop();
+            }
+            pos = self.positions.next(pos);
+        }
+    }
+}
@@ -32,11 +32,21 @@
 
 use std::error;
 use std::fmt;
+#[cfg(feature = "blocking")]
+use std::thread;
 
+use crate::blocking::Waiters;
 use crate::bounded::Bounded;
+use crate::recycle::Recycled;
 use crate::unbounded::Unbounded;
 
+pub use crate::recycle::{Clear, DefaultRecycle, Recycle, Ref, RefMut};
+
+mod blocking;
 mod bounded;
+mod recycle;
+mod ring;
+mod spsc;
 mod unbounded;
 
 /// A concurrent queue.
@@ -56,7 +66,10 @@ mod unbounded;
 /// assert_eq!(q.pop(), Ok('b'));
 /// assert_eq!(q.pop(), Err(PopError::Empty));
 /// ```
-pub struct ConcurrentQueue<T>(Inner<T>);
+pub struct ConcurrentQueue<T> {
+    inner: Inner<T>,
+    waiters: Waiters,
+}
 
 unsafe impl<T: Send> Send for ConcurrentQueue<T> {}
 unsafe impl<T: Send> Sync for ConcurrentQueue<T> {}
@@ -64,9 +77,20 @@ unsafe impl<T: Send> Sync for ConcurrentQueue<T> {}
 enum Inner<T> {
     Bounded(Bounded<T>),
     Unbounded(Unbounded<T>),
+    Recycled(Recycled<T, Box<dyn Recycle<T> + Send + Sync>>),
+    BoundedSpsc(spsc::Bounded<T>),
+    UnboundedSpsc(spsc::Unbounded<T>),
 }
 
 impl<T> ConcurrentQueue<T> {
+    /// Wraps an `Inner` with a fresh set of waiters.
+    fn from_inner(inner: Inner<T>) -> ConcurrentQueue<T> {
+        ConcurrentQueue {
+            inner,
+            waiters: Waiters::new(),
+        }
+    }
+
     /// Creates a new bounded queue.
     ///
     /// The queue allocates enough space for `cap` items.
@@ -83,7 +107,7 @@ impl<T> ConcurrentQueue<T> {
     /// let q = ConcurrentQueue::<i32>::bounded(100);
     /// ```
     pub fn bounded(cap: usize) -> ConcurrentQueue<T> {
-        ConcurrentQueue(Inner::Bounded(Bounded::new(cap)))
+        ConcurrentQueue::from_inner(Inner::Bounded(Bounded::new(cap)))
     }
 
     /// Creates a new unbounded queue.
@@ -96,7 +120,97 @@ impl<T> ConcurrentQueue<T> {
     /// let q = ConcurrentQueue::<i32>::unbounded();
     /// ```
     pub fn unbounded() -> ConcurrentQueue<T> {
-        ConcurrentQueue(Inner::Unbounded(Unbounded::new()))
+        ConcurrentQueue::from_inner(Inner::Unbounded(Unbounded::new()))
+    }
+
+    /// Creates a new bounded queue that recycles its elements' allocations.
+    ///
+    /// Every slot is pre-filled by calling `recycle.new_element()`, and stays populated for the
+    /// lifetime of the queue. Use [`push_ref`]/[`pop_ref`] to fill and read slots in place instead
+    /// of moving values in and out, so queues of large `T` don't allocate and free per message.
+    ///
+    /// # Panics
+    ///
+    /// If the capacity is zero, this constructor will panic.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use concurrent_queue::{ConcurrentQueue, DefaultRecycle, PushError};
+    ///
+    /// let q = ConcurrentQueue::<Vec<u8>>::bounded_with_recycle(1, DefaultRecycle);
+    ///
+    /// {
+    ///     let mut slot = q.push_ref().unwrap();
+    ///     slot.extend_from_slice(b"hi");
+    /// }
+    ///
+    /// // The queue is now full, so a second push errors instead of overwriting the first value.
+    /// assert!(matches!(q.push(b"bye".to_vec()), Err(PushError::Full(_))));
+    ///
+    /// let popped = q.pop_ref().unwrap();
+    /// assert_eq!(&*popped, b"hi");
+    /// ```
+    ///
+    /// [`push_ref`]: ConcurrentQueue::push_ref
+    /// [`pop_ref`]: ConcurrentQueue::pop_ref
+    pub fn bounded_with_recycle<R>(cap: usize, recycle: R) -> ConcurrentQueue<T>
+    where
+        R: Recycle<T> + Send + Sync + 'static,
+    {
+        ConcurrentQueue::from_inner(Inner::Recycled(Recycled::new(cap, Box::new(recycle))))
+    }
+
+    /// Creates a new unbounded queue specialized for a single producer and single consumer.
+    ///
+    /// This selects an implementation tuned for exactly one producer thread and one consumer
+    /// thread: the head and tail cursors are updated with plain relaxed/acquire-release atomics
+    /// instead of a CAS loop, and the queue grows by appending fixed-size segments. Callers who
+    /// know their topology get lower latency through the same `push`/`pop`/`close`/`len` contract.
+    ///
+    /// Pushing from more than one thread at a time, or popping from more than one thread at a
+    /// time, is not supported: each end's single-owner invariant is enforced at runtime, and a
+    /// `push`/`push_blocking` (or `pop`/`pop_blocking`) call that overlaps with another on the
+    /// same end panics rather than racing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use concurrent_queue::ConcurrentQueue;
+    ///
+    /// let q = ConcurrentQueue::<i32>::spsc();
+    /// ```
+    pub fn spsc() -> ConcurrentQueue<T> {
+        ConcurrentQueue::from_inner(Inner::UnboundedSpsc(spsc::Unbounded::new()))
+    }
+
+    /// Creates a new bounded queue specialized for a single producer and single consumer.
+    ///
+    /// See [`spsc`] for the tradeoffs of the SPSC fast path.
+    ///
+    /// # Panics
+    ///
+    /// If the capacity is zero, this constructor will panic.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use concurrent_queue::{ConcurrentQueue, PushError};
+    ///
+    /// let q = ConcurrentQueue::<i32>::bounded_spsc(1);
+    ///
+    /// assert_eq!(q.push(10), Ok(()));
+    ///
+    /// // Push errors because the queue is now full.
+    /// assert_eq!(q.push(20), Err(PushError::Full(20)));
+    ///
+    /// assert_eq!(q.pop(), Ok(10));
+    /// assert_eq!(q.push(20), Ok(()));
+    /// ```
+    ///
+    /// [`spsc`]: ConcurrentQueue::spsc
+    pub fn bounded_spsc(cap: usize) -> ConcurrentQueue<T> {
+        ConcurrentQueue::from_inner(Inner::BoundedSpsc(spsc::Bounded::new(cap)))
     }
 
     /// Attempts to push an item into the queue.
@@ -129,10 +243,17 @@ impl<T> ConcurrentQueue<T> {
     /// assert_eq!(q.push(20), Err(PushError::Closed(20)));
     /// ```
     pub fn push(&self, value: T) -> Result<(), PushError<T>> {
-        match &self.0 {
+        let result = match &self.inner {
             Inner::Bounded(q) => q.push(value),
             Inner::Unbounded(q) => q.push(value),
+            Inner::Recycled(q) => q.push(value),
+            Inner::BoundedSpsc(q) => q.push(value),
+            Inner::UnboundedSpsc(q) => q.push(value),
+        };
+        if result.is_ok() {
+            self.waiters.wake_one_consumer();
         }
+        result
     }
 
     /// Attempts to pop an item from the queue.
@@ -161,9 +282,104 @@ impl<T> ConcurrentQueue<T> {
     /// assert_eq!(q.pop(), Err(PopError::Closed));
     /// ```
     pub fn pop(&self) -> Result<T, PopError> {
-        match &self.0 {
+        let result = match &self.inner {
             Inner::Bounded(q) => q.pop(),
             Inner::Unbounded(q) => q.pop(),
+            Inner::Recycled(q) => q.pop(),
+            Inner::BoundedSpsc(q) => q.pop(),
+            Inner::UnboundedSpsc(q) => q.pop(),
+        };
+        if result.is_ok() {
+            self.waiters.wake_one_producer();
+        }
+        result
+    }
+
+    /// Pushes an item into the queue, blocking the calling thread until there is space.
+    ///
+    /// Parks the calling thread while the queue is full, waking up as soon as another thread
+    /// pops an item or closes the queue. Returns `PushError::Closed` immediately if the queue is
+    /// already closed, and `close()` wakes every thread parked in this call so none of them are
+    /// left stranded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use concurrent_queue::{ConcurrentQueue, PushError};
+    ///
+    /// let q = ConcurrentQueue::bounded(1);
+    ///
+    /// assert_eq!(q.push_blocking(10), Ok(()));
+    ///
+    /// q.close();
+    /// assert_eq!(q.push_blocking(20), Err(PushError::Closed(20)));
+    /// ```
+    #[cfg(feature = "blocking")]
+    pub fn push_blocking(&self, mut value: T) -> Result<(), PushError<T>> {
+        loop {
+            match self.push(value) {
+                Ok(()) => return Ok(()),
+                Err(PushError::Closed(v)) => return Err(PushError::Closed(v)),
+                Err(PushError::Full(v)) => value = v,
+            }
+
+            // Register before re-checking so a pop that frees a slot after our failed attempt is
+            // never missed: either the re-check sees the slot or the pop unparks us. This
+            // re-registers on every retry, not just the first: a wakeup only means *some* slot
+            // freed up, not that this thread won the race for it, so a losing retry must
+            // re-register or it can be parked forever with no one left to wake it. `register_producer` is idempotent per
+            // thread, so retrying doesn't pile up duplicate entries.
+            self.waiters.register_producer();
+
+            match self.push(value) {
+                Ok(()) => return Ok(()),
+                Err(PushError::Closed(v)) => return Err(PushError::Closed(v)),
+                Err(PushError::Full(v)) => value = v,
+            }
+
+            thread::park();
+        }
+    }
+
+    /// Pops an item from the queue, blocking the calling thread until one is available.
+    ///
+    /// Parks the calling thread while the queue is empty, waking up as soon as another thread
+    /// pushes an item or closes the queue. Once the queue is closed, any remaining items are
+    /// still drained before this returns `PopError::Closed`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use concurrent_queue::{ConcurrentQueue, PopError};
+    ///
+    /// let q = ConcurrentQueue::bounded(1);
+    /// q.push(10).unwrap();
+    /// q.close();
+    ///
+    /// assert_eq!(q.pop_blocking(), Ok(10));
+    /// assert_eq!(q.pop_blocking(), Err(PopError::Closed));
+    /// ```
+    #[cfg(feature = "blocking")]
+    pub fn pop_blocking(&self) -> Result<T, PopError> {
+        loop {
+            match self.pop() {
+                Ok(v) => return Ok(v),
+                Err(PopError::Closed) => return Err(PopError::Closed),
+                Err(PopError::Empty) => {}
+            }
+
+            // See push_blocking: this re-registers on every retry (idempotent per thread via
+            // register_consumer), since a wakeup only means something was pushed, not that this
+            // thread won the race to pop it.
+            self.waiters.register_consumer();
+
+            match self.pop() {
+                Ok(v) => return Ok(v),
+                Err(PopError::Closed) => return Err(PopError::Closed),
+                Err(PopError::Empty) => {}
+            }
+
+            thread::park();
         }
     }
 
@@ -181,9 +397,12 @@ impl<T> ConcurrentQueue<T> {
     /// assert!(!q.is_empty());
     /// ```
     pub fn is_empty(&self) -> bool {
-        match &self.0 {
+        match &self.inner {
             Inner::Bounded(q) => q.is_empty(),
             Inner::Unbounded(q) => q.is_empty(),
+            Inner::Recycled(q) => q.is_empty(),
+            Inner::BoundedSpsc(q) => q.is_empty(),
+            Inner::UnboundedSpsc(q) => q.is_empty(),
         }
     }
 
@@ -203,9 +422,12 @@ impl<T> ConcurrentQueue<T> {
     /// assert!(q.is_full());
     /// ```
     pub fn is_full(&self) -> bool {
-        match &self.0 {
+        match &self.inner {
             Inner::Bounded(q) => q.is_full(),
             Inner::Unbounded(q) => q.is_full(),
+            Inner::Recycled(q) => q.is_full(),
+            Inner::BoundedSpsc(q) => q.is_full(),
+            Inner::UnboundedSpsc(q) => q.is_full(),
         }
     }
 
@@ -226,9 +448,12 @@ impl<T> ConcurrentQueue<T> {
     /// assert_eq!(q.len(), 2);
     /// ```
     pub fn len(&self) -> usize {
-        match &self.0 {
+        match &self.inner {
             Inner::Bounded(q) => q.len(),
             Inner::Unbounded(q) => q.len(),
+            Inner::Recycled(q) => q.len(),
+            Inner::BoundedSpsc(q) => q.len(),
+            Inner::UnboundedSpsc(q) => q.len(),
         }
     }
 
@@ -248,9 +473,12 @@ impl<T> ConcurrentQueue<T> {
     /// assert_eq!(q.capacity(), None);
     /// ```
     pub fn capacity(&self) -> Option<usize> {
-        match &self.0 {
+        match &self.inner {
             Inner::Bounded(q) => Some(q.capacity()),
             Inner::Unbounded(_) => None,
+            Inner::Recycled(q) => Some(q.capacity()),
+            Inner::BoundedSpsc(q) => Some(q.capacity()),
+            Inner::UnboundedSpsc(_) => None,
         }
     }
 
@@ -282,10 +510,15 @@ impl<T> ConcurrentQueue<T> {
     /// assert_eq!(q.pop(), Err(PopError::Closed));
     /// ```
     pub fn close(&self) -> bool {
-        match &self.0 {
+        let closed_now = match &self.inner {
             Inner::Bounded(q) => q.close(),
             Inner::Unbounded(q) => q.close(),
-        }
+            Inner::Recycled(q) => q.close(),
+            Inner::BoundedSpsc(q) => q.close(),
+            Inner::UnboundedSpsc(q) => q.close(),
+        };
+        self.waiters.wake_all();
+        closed_now
     }
 
     /// Returns `true` if the queue is closed.
@@ -302,9 +535,99 @@ impl<T> ConcurrentQueue<T> {
     /// assert!(q.is_closed());
     /// ```
     pub fn is_closed(&self) -> bool {
-        match &self.0 {
+        match &self.inner {
             Inner::Bounded(q) => q.is_closed(),
             Inner::Unbounded(q) => q.is_closed(),
+            Inner::Recycled(q) => q.is_closed(),
+            Inner::BoundedSpsc(q) => q.is_closed(),
+            Inner::UnboundedSpsc(q) => q.is_closed(),
+        }
+    }
+
+    /// Closes the queue and returns an iterator over the items still buffered in it.
+    ///
+    /// This lets shutdown code reclaim in-flight items in one pass, e.g. to run destructors or
+    /// re-route work, instead of looping on [`pop`] and branching on [`PopError`].
+    ///
+    /// The returned iterator is safe to use alongside producers that raced with the close: it
+    /// simply pops until it observes [`PopError::Closed`], and `len()` reflects each removal as
+    /// the iterator advances.
+    ///
+    /// [`pop`]: ConcurrentQueue::pop
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use concurrent_queue::ConcurrentQueue;
+    ///
+    /// let q = ConcurrentQueue::unbounded();
+    /// q.push(1).unwrap();
+    /// q.push(2).unwrap();
+    ///
+    /// assert_eq!(q.close_and_drain().collect::<Vec<_>>(), vec![1, 2]);
+    /// assert!(q.is_closed());
+    /// ```
+    pub fn close_and_drain(&self) -> Drain<'_, T> {
+        self.close();
+        Drain { queue: self }
+    }
+
+    /// Returns an iterator that empties the queue without closing it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use concurrent_queue::ConcurrentQueue;
+    ///
+    /// let q = ConcurrentQueue::unbounded();
+    /// q.push(1).unwrap();
+    /// q.push(2).unwrap();
+    ///
+    /// assert_eq!(q.drain().collect::<Vec<_>>(), vec![1, 2]);
+    /// assert!(!q.is_closed());
+    /// ```
+    pub fn drain(&self) -> Drain<'_, T> {
+        Drain { queue: self }
+    }
+
+    /// Reserves the next slot for a producer to fill in place.
+    ///
+    /// The returned guard derefs to the slot's already-allocated element so it can be filled in
+    /// place; the push is committed when the guard is dropped, waking a consumer blocked in
+    /// [`pop_blocking`](Self::pop_blocking) the same as [`push`](Self::push) does.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the queue was not created with [`bounded_with_recycle`].
+    ///
+    /// [`bounded_with_recycle`]: ConcurrentQueue::bounded_with_recycle
+    pub fn push_ref(&self) -> Result<RefMut<'_, T>, PushError<()>> {
+        match &self.inner {
+            Inner::Recycled(q) => q.push_ref(&self.waiters),
+            Inner::Bounded(_) | Inner::Unbounded(_) | Inner::BoundedSpsc(_) | Inner::UnboundedSpsc(_) => {
+                panic!("push_ref can only be used on a queue created with bounded_with_recycle")
+            }
+        }
+    }
+
+    /// Reserves the next slot for a consumer to read in place.
+    ///
+    /// When the returned guard is dropped, the element is handed to the queue's [`Recycle`]
+    /// strategy so its allocation is kept for the next producer instead of being freed, and a
+    /// producer blocked in [`push_blocking`](Self::push_blocking) is woken, the same as
+    /// [`pop`](Self::pop) does.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the queue was not created with [`bounded_with_recycle`].
+    ///
+    /// [`bounded_with_recycle`]: ConcurrentQueue::bounded_with_recycle
+    pub fn pop_ref(&self) -> Result<Ref<'_, T, Box<dyn Recycle<T> + Send + Sync>>, PopError> {
+        match &self.inner {
+            Inner::Recycled(q) => q.pop_ref(&self.waiters),
+            Inner::Bounded(_) | Inner::Unbounded(_) | Inner::BoundedSpsc(_) | Inner::UnboundedSpsc(_) => {
+                panic!("pop_ref can only be used on a queue created with bounded_with_recycle")
+            }
         }
     }
 }
@@ -378,3 +701,24 @@ impl<T> fmt::Display for PushError<T> {
         }
     }
 }
+
+/// An iterator that removes items from a [`ConcurrentQueue`].
+///
+/// Created by [`ConcurrentQueue::close_and_drain`] and [`ConcurrentQueue::drain`].
+pub struct Drain<'a, T> {
+    queue: &'a ConcurrentQueue<T>,
+}
+
+impl<T> Iterator for Drain<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.queue.pop().ok()
+    }
+}
+
+impl<T> fmt::Debug for Drain<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Drain").field("len", &self.queue.len()).finish()
+    }
+}
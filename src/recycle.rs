@@ -0,0 +1,366 @@
+//! Allocation-reuse support for queues of large elements.
+//!
+//! A queue created with [`ConcurrentQueue::bounded_with_recycle`] keeps every slot's element
+//! alive for the lifetime of the queue. Instead of moving values in and out on every `push`/`pop`,
+//! producers and consumers borrow a slot in place through [`push_ref`]/[`pop_ref`], so queues of
+//! large `T` (buffers, `Vec`s, strings) don't allocate and free on every message.
+//!
+//! [`ConcurrentQueue::bounded_with_recycle`]: crate::ConcurrentQueue::bounded_with_recycle
+//! [`push_ref`]: crate::ConcurrentQueue::push_ref
+//! [`pop_ref`]: crate::ConcurrentQueue::pop_ref
+
+use std::cell::UnsafeCell;
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::ptr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::blocking::Waiters;
+use crate::ring::{Positions, CLOSED};
+use crate::{PopError, PushError};
+
+/// A strategy for creating and reusing the elements behind a [recycling] queue.
+///
+/// [recycling]: crate::ConcurrentQueue::bounded_with_recycle
+pub trait Recycle<T> {
+    /// Creates a new element to occupy a queue slot.
+    fn new_element(&self) -> T;
+
+    /// Prepares a popped element to be reused by a future push.
+    fn recycle(&self, element: &mut T);
+}
+
+impl<T, X: Recycle<T> + ?Sized> Recycle<T> for Box<X> {
+    fn new_element(&self) -> T {
+        (**self).new_element()
+    }
+
+    fn recycle(&self, element: &mut T) {
+        (**self).recycle(element)
+    }
+}
+
+/// A type that can be cleared in place, keeping its allocation.
+pub trait Clear {
+    /// Removes all contents of `self` without releasing its allocation.
+    fn clear(&mut self);
+}
+
+impl<T> Clear for Vec<T> {
+    fn clear(&mut self) {
+        Vec::clear(self)
+    }
+}
+
+impl Clear for String {
+    fn clear(&mut self) {
+        String::clear(self)
+    }
+}
+
+/// The default [`Recycle`] strategy.
+///
+/// Elements are created with [`Default`] and recycled with [`Clear`], so a previously pushed
+/// allocation is kept and reused by the next producer instead of being dropped.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DefaultRecycle;
+
+impl<T: Default + Clear> Recycle<T> for DefaultRecycle {
+    fn new_element(&self) -> T {
+        T::default()
+    }
+
+    fn recycle(&self, element: &mut T) {
+        element.clear();
+    }
+}
+
+/// A slot in a recycling queue.
+struct Slot<T> {
+    stamp: AtomicUsize,
+    value: UnsafeCell<T>,
+}
+
+/// A bounded queue whose slots are always populated, so elements can be borrowed in place.
+pub(crate) struct Recycled<T, R> {
+    /// Head/tail position bookkeeping, shared with [`crate::bounded::Bounded`].
+    positions: Positions,
+
+    buffer: Box<[Slot<T>]>,
+    recycle: R,
+}
+
+unsafe impl<T: Send, R: Send> Send for Recycled<T, R> {}
+unsafe impl<T: Send, R: Sync> Sync for Recycled<T, R> {}
+
+impl<T, R: Recycle<T>> Recycled<T, R> {
+    /// Creates a new recycling queue, pre-filling every slot via `recycle.new_element()`.
+    pub(crate) fn new(cap: usize, recycle: R) -> Recycled<T, R> {
+        let positions = Positions::new(cap);
+
+        let buffer = (0..cap)
+            .map(|i| Slot {
+                stamp: AtomicUsize::new(i),
+                value: UnsafeCell::new(recycle.new_element()),
+            })
+            .collect();
+
+        Recycled {
+            positions,
+            buffer,
+            recycle,
+        }
+    }
+
+    /// Claims the next slot for a producer, returning it along with the stamp to store once the
+    /// producer is done filling it in.
+    fn claim_push(&self) -> Result<(&Slot<T>, usize), PushError<()>> {
+        let mut tail = self.positions.tail.load(Ordering::Relaxed);
+
+        loop {
+            if tail & CLOSED != 0 {
+                return Err(PushError::Closed(()));
+            }
+
+            let index = self.positions.index(tail);
+            let slot = &self.buffer[index];
+            let stamp = slot.stamp.load(Ordering::Acquire);
+
+            let diff = stamp as isize - tail as isize;
+
+            if diff == 0 {
+                let new_tail = self.positions.next(tail);
+
+                match self.positions.tail.compare_exchange_weak(
+                    tail,
+                    new_tail,
+                    Ordering::SeqCst,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => return Ok((slot, tail + 1)),
+                    Err(t) => tail = t,
+                }
+            } else if diff < 0 {
+                return Err(PushError::Full(()));
+            } else {
+                tail = self.positions.tail.load(Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Claims the next slot for a consumer, returning it along with the stamp to store once the
+    /// consumer is done with it.
+    fn claim_pop(&self) -> Result<(&Slot<T>, usize), PopError> {
+        let mut head = self.positions.head.load(Ordering::Relaxed);
+
+        loop {
+            let index = self.positions.index(head);
+            let slot = &self.buffer[index];
+            let stamp = slot.stamp.load(Ordering::Acquire);
+
+            let diff = stamp as isize - (head + 1) as isize;
+
+            if diff == 0 {
+                let new_head = self.positions.next(head);
+
+                match self.positions.head.compare_exchange_weak(
+                    head,
+                    new_head,
+                    Ordering::SeqCst,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => return Ok((slot, head + self.positions.one_lap)),
+                    Err(h) => head = h,
+                }
+            } else if diff < 0 {
+                let tail = self.positions.tail.load(Ordering::SeqCst);
+
+                if tail & !CLOSED == head {
+                    return Err(if tail & CLOSED != 0 {
+                        PopError::Closed
+                    } else {
+                        PopError::Empty
+                    });
+                }
+
+                head = self.positions.head.load(Ordering::Relaxed);
+            } else {
+                head = self.positions.head.load(Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Reserves the next slot for a producer to fill in place.
+    ///
+    /// `waiters` is woken for a consumer once the returned guard is dropped and the push is
+    /// committed, matching what [`ConcurrentQueue::push`](crate::ConcurrentQueue::push) does for
+    /// the same event.
+    pub(crate) fn push_ref<'a>(&'a self, waiters: &'a Waiters) -> Result<RefMut<'a, T>, PushError<()>> {
+        let (slot, stamp) = self.claim_push()?;
+        Ok(RefMut { slot, stamp, waiters })
+    }
+
+    /// Reserves the next slot for a consumer to read in place.
+    ///
+    /// `waiters` is woken for a producer once the returned guard is dropped and the pop is
+    /// committed, matching what [`ConcurrentQueue::pop`](crate::ConcurrentQueue::pop) does for
+    /// the same event.
+    pub(crate) fn pop_ref<'a>(&'a self, waiters: &'a Waiters) -> Result<Ref<'a, T, R>, PopError> {
+        let (slot, stamp) = self.claim_pop()?;
+        Ok(Ref {
+            slot,
+            recycle: &self.recycle,
+            waiters,
+            stamp,
+        })
+    }
+
+    /// Attempts to push an item into the queue.
+    pub(crate) fn push(&self, value: T) -> Result<(), PushError<T>> {
+        match self.claim_push() {
+            Ok((slot, stamp)) => {
+                unsafe {
+                    *slot.value.get() = value;
+                }
+                slot.stamp.store(stamp, Ordering::Release);
+                Ok(())
+            }
+            Err(PushError::Full(())) => Err(PushError::Full(value)),
+            Err(PushError::Closed(())) => Err(PushError::Closed(value)),
+        }
+    }
+
+    /// Attempts to pop an item from the queue, leaving a freshly created element behind.
+    ///
+    /// The popped value is handed to the caller as-is, so it never goes through
+    /// [`Recycle::recycle`] (there is nothing to recycle it *into* — the slot's replacement is a
+    /// brand new element, not this value). Queues that want the recycling benefit on the consumer
+    /// side should use [`pop_ref`](Self::pop_ref) instead, which reads in place.
+    pub(crate) fn pop(&self) -> Result<T, PopError> {
+        let (slot, stamp) = self.claim_pop()?;
+        let mut taken = self.recycle.new_element();
+        unsafe {
+            ptr::swap(&mut taken, slot.value.get());
+        }
+        slot.stamp.store(stamp, Ordering::Release);
+        Ok(taken)
+    }
+
+    /// Returns `true` if the queue is empty.
+    pub(crate) fn is_empty(&self) -> bool {
+        let head = self.positions.head.load(Ordering::SeqCst);
+        let tail = self.positions.tail.load(Ordering::SeqCst);
+        head == tail & !CLOSED
+    }
+
+    /// Returns `true` if the queue is full.
+    pub(crate) fn is_full(&self) -> bool {
+        let head = self.positions.head.load(Ordering::SeqCst);
+        let tail = self.positions.tail.load(Ordering::SeqCst) & !CLOSED;
+        self.positions.count(tail) - self.positions.count(head) == self.positions.cap
+    }
+
+    /// Returns the number of items in the queue.
+    pub(crate) fn len(&self) -> usize {
+        loop {
+            let tail = self.positions.tail.load(Ordering::SeqCst);
+            let head = self.positions.head.load(Ordering::SeqCst);
+
+            if self.positions.tail.load(Ordering::SeqCst) == tail {
+                return self.positions.count(tail & !CLOSED) - self.positions.count(head);
+            }
+        }
+    }
+
+    /// Returns the capacity of the queue.
+    pub(crate) fn capacity(&self) -> usize {
+        self.positions.cap
+    }
+
+    /// Closes the queue, returning `true` if this call closed it.
+    pub(crate) fn close(&self) -> bool {
+        let tail = self.positions.tail.fetch_or(CLOSED, Ordering::SeqCst);
+        tail & CLOSED == 0
+    }
+
+    /// Returns `true` if the queue is closed.
+    pub(crate) fn is_closed(&self) -> bool {
+        self.positions.tail.load(Ordering::SeqCst) & CLOSED != 0
+    }
+}
+
+/// A guard borrowing a queue slot for a producer to fill in place.
+///
+/// The slot's existing (recycled) allocation is exposed through [`DerefMut`] for the producer to
+/// write into. The push is committed when the guard is dropped, which also wakes a consumer
+/// blocked in [`pop_blocking`](crate::ConcurrentQueue::pop_blocking), the same as [`push`] does.
+///
+/// [`push`]: crate::ConcurrentQueue::push
+pub struct RefMut<'a, T> {
+    slot: &'a Slot<T>,
+    stamp: usize,
+    waiters: &'a Waiters,
+}
+
+impl<T> Deref for RefMut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.slot.value.get() }
+    }
+}
+
+impl<T> DerefMut for RefMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.slot.value.get() }
+    }
+}
+
+impl<T> Drop for RefMut<'_, T> {
+    fn drop(&mut self) {
+        self.slot.stamp.store(self.stamp, Ordering::Release);
+        self.waiters.wake_one_consumer();
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for RefMut<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+/// A guard borrowing a queue slot for a consumer to read in place.
+///
+/// When the guard is dropped, the element is handed to [`Recycle::recycle`] so its allocation is
+/// kept for the next producer instead of being freed, and a producer blocked in
+/// [`push_blocking`](crate::ConcurrentQueue::push_blocking) is woken, the same as [`pop`] does.
+///
+/// [`pop`]: crate::ConcurrentQueue::pop
+pub struct Ref<'a, T, R: Recycle<T>> {
+    slot: &'a Slot<T>,
+    recycle: &'a R,
+    waiters: &'a Waiters,
+    stamp: usize,
+}
+
+impl<T, R: Recycle<T>> Deref for Ref<'_, T, R> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.slot.value.get() }
+    }
+}
+
+impl<T, R: Recycle<T>> Drop for Ref<'_, T, R> {
+    fn drop(&mut self) {
+        self.recycle.recycle(unsafe { &mut *self.slot.value.get() });
+        self.slot.stamp.store(self.stamp, Ordering::Release);
+        self.waiters.wake_one_producer();
+    }
+}
+
+impl<T: fmt::Debug, R: Recycle<T>> fmt::Debug for Ref<'_, T, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
@@ -0,0 +1,76 @@
+//! Shared Vyukov-style position arithmetic for the array-based MPMC ring buffers.
+//!
+//! [`Bounded`] and [`Recycled`] both implement Dmitry Vyukov's bounded MPMC queue: each slot
+//! carries its own sequence number (the slot's "stamp"), so pushes and pops only ever need a
+//! single CAS on the shared head/tail counter rather than a lock around the whole buffer. This
+//! module holds the head/tail position bookkeeping they share (slot index, next position, item
+//! count), so that arithmetic exists in exactly one place; the stamp checks and CAS loops of
+//! push and pop are written out in each of the two queue types.
+//!
+//! [`Bounded`]: crate::bounded::Bounded
+//! [`Recycled`]: crate::recycle::Recycled
+
+use std::sync::atomic::AtomicUsize;
+
+/// The bit of `tail` that marks the queue as closed.
+pub(crate) const CLOSED: usize = 1 << (usize::BITS - 1);
+
+/// Head/tail position bookkeeping for a `cap`-slot ring.
+///
+/// Positions are not bare indices modulo `cap`: each lap around the buffer advances by
+/// `one_lap`, a value strictly greater than `cap`. That slack is what lets a slot's stamp tell a
+/// "just pushed" slot apart from a "just popped" one even at `cap == 1`, where stepping by
+/// exactly `cap` per lap would make the position right after a push and the position a pop
+/// produces numerically identical (there being only one slot to disambiguate with).
+pub(crate) struct Positions {
+    /// The head of the queue.
+    pub(crate) head: AtomicUsize,
+
+    /// The tail of the queue.
+    ///
+    /// The highest bit of `tail` marks the queue as closed.
+    pub(crate) tail: AtomicUsize,
+
+    /// The queue capacity.
+    pub(crate) cap: usize,
+
+    /// The distance between successive positions that map to the same slot index.
+    pub(crate) one_lap: usize,
+}
+
+impl Positions {
+    /// Creates fresh head/tail positions for a ring of `cap` slots.
+    pub(crate) fn new(cap: usize) -> Positions {
+        assert!(cap > 0, "capacity must be positive");
+
+        Positions {
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+            cap,
+            one_lap: cap + 1,
+        }
+    }
+
+    /// The slot index a position refers to.
+    pub(crate) fn index(&self, pos: usize) -> usize {
+        pos % self.one_lap
+    }
+
+    /// The position this slot moves to on its next use, skipping the per-lap slack index.
+    pub(crate) fn next(&self, pos: usize) -> usize {
+        let index = self.index(pos);
+        if index + 1 < self.cap {
+            pos + 1
+        } else {
+            pos - index + self.one_lap
+        }
+    }
+
+    /// The number of pushes (or pops) it took to reach `pos`, with the per-lap slack index
+    /// divided back out, so subtracting two of these gives the true item count between them.
+    pub(crate) fn count(&self, pos: usize) -> usize {
+        let lap = pos / self.one_lap;
+        let index = self.index(pos);
+        lap * self.cap + index
+    }
+}
@@ -0,0 +1,341 @@
+//! A queue specialized for exactly one producer and one consumer.
+//!
+//! Because only one thread is meant to ever touch each end, the head and tail cursors can be
+//! plain atomics updated with relaxed/acquire-release pairs instead of a CAS loop, mirroring the
+//! non-blocking list-of-segments design used by dedicated SPSC queues. [`SingleAccess`] enforces
+//! the one-thread-per-end invariant this relies on: pushing (or popping) from two threads at once
+//! panics instead of racing on the unsynchronized slot writes.
+
+use std::cell::UnsafeCell;
+use std::mem::MaybeUninit;
+use std::ptr;
+use std::sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize, Ordering};
+
+use crate::{PopError, PushError};
+
+/// Guards one end of an SPSC queue against being entered from more than one thread at a time.
+///
+/// The SPSC fast path's whole point is to avoid synchronizing producer-vs-producer (or
+/// consumer-vs-consumer) access, so it can't tolerate two threads racing on the same end the way
+/// the MPMC path does. A doc comment alone can't stop safe code from sharing a `Sync` queue
+/// across two producer threads, so this turns that misuse into a deterministic panic instead of
+/// the unsynchronized slot writes silently racing: entering twice without an intervening exit
+/// means two threads are mid-call at once.
+struct SingleAccess(AtomicBool);
+
+impl SingleAccess {
+    fn new() -> SingleAccess {
+        SingleAccess(AtomicBool::new(false))
+    }
+
+    fn enter(&self) -> AccessGuard<'_> {
+        if self.0.swap(true, Ordering::Acquire) {
+            panic!(
+                "concurrent-queue: an spsc queue's producer or consumer end was accessed from \
+                 more than one thread at a time"
+            );
+        }
+        AccessGuard(&self.0)
+    }
+}
+
+/// Released when dropped, marking this end of the queue free for the next call to enter.
+struct AccessGuard<'a>(&'a AtomicBool);
+
+impl Drop for AccessGuard<'_> {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Release);
+    }
+}
+
+/// A bounded single-producer single-consumer queue.
+pub(crate) struct Bounded<T> {
+    buffer: Box<[UnsafeCell<MaybeUninit<T>>]>,
+    cap: usize,
+    head: AtomicUsize,
+    tail: AtomicUsize,
+    closed: AtomicBool,
+    producer: SingleAccess,
+    consumer: SingleAccess,
+}
+
+unsafe impl<T: Send> Send for Bounded<T> {}
+unsafe impl<T: Send> Sync for Bounded<T> {}
+
+impl<T> Bounded<T> {
+    /// Creates a new bounded SPSC queue with the given capacity.
+    pub(crate) fn new(cap: usize) -> Bounded<T> {
+        assert!(cap > 0, "capacity must be positive");
+
+        Bounded {
+            buffer: (0..cap).map(|_| UnsafeCell::new(MaybeUninit::uninit())).collect(),
+            cap,
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+            closed: AtomicBool::new(false),
+            producer: SingleAccess::new(),
+            consumer: SingleAccess::new(),
+        }
+    }
+
+    /// Attempts to push an item into the queue.
+    ///
+    /// Panics if called concurrently with another `push` call.
+    pub(crate) fn push(&self, value: T) -> Result<(), PushError<T>> {
+        let _guard = self.producer.enter();
+
+        if self.closed.load(Ordering::Acquire) {
+            return Err(PushError::Closed(value));
+        }
+
+        let tail = self.tail.load(Ordering::Relaxed);
+        let head = self.head.load(Ordering::Acquire);
+
+        if tail.wrapping_sub(head) == self.cap {
+            return Err(PushError::Full(value));
+        }
+
+        let index = tail % self.cap;
+        unsafe {
+            self.buffer[index].get().write(MaybeUninit::new(value));
+        }
+        self.tail.store(tail.wrapping_add(1), Ordering::Release);
+        Ok(())
+    }
+
+    /// Attempts to pop an item from the queue.
+    ///
+    /// Panics if called concurrently with another `pop` call.
+    pub(crate) fn pop(&self) -> Result<T, PopError> {
+        let _guard = self.consumer.enter();
+
+        let head = self.head.load(Ordering::Relaxed);
+        let tail = self.tail.load(Ordering::Acquire);
+
+        if head == tail {
+            return Err(if self.closed.load(Ordering::Acquire) {
+                PopError::Closed
+            } else {
+                PopError::Empty
+            });
+        }
+
+        let index = head % self.cap;
+        let value = unsafe { self.buffer[index].get().read().assume_init() };
+        self.head.store(head.wrapping_add(1), Ordering::Release);
+        Ok(value)
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.head.load(Ordering::SeqCst) == self.tail.load(Ordering::SeqCst)
+    }
+
+    pub(crate) fn is_full(&self) -> bool {
+        let head = self.head.load(Ordering::SeqCst);
+        let tail = self.tail.load(Ordering::SeqCst);
+        tail.wrapping_sub(head) == self.cap
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        let head = self.head.load(Ordering::SeqCst);
+        let tail = self.tail.load(Ordering::SeqCst);
+        tail.wrapping_sub(head)
+    }
+
+    pub(crate) fn capacity(&self) -> usize {
+        self.cap
+    }
+
+    pub(crate) fn close(&self) -> bool {
+        !self.closed.swap(true, Ordering::SeqCst)
+    }
+
+    pub(crate) fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
+}
+
+impl<T> Drop for Bounded<T> {
+    fn drop(&mut self) {
+        let head = *self.head.get_mut();
+        let tail = *self.tail.get_mut();
+        let mut index = head;
+
+        while index != tail {
+            unsafe {
+                (*self.buffer[index % self.cap].get()).assume_init_drop();
+            }
+            index = index.wrapping_add(1);
+        }
+    }
+}
+
+/// The number of slots in each segment of an unbounded SPSC queue.
+const SEGMENT_CAP: usize = 32;
+
+/// A fixed-size node in the list of segments backing an unbounded SPSC queue.
+struct Segment<T> {
+    slots: [UnsafeCell<MaybeUninit<T>>; SEGMENT_CAP],
+    next: AtomicPtr<Segment<T>>,
+}
+
+impl<T> Segment<T> {
+    fn new() -> Box<Segment<T>> {
+        Box::new(Segment {
+            // Safety: `UnsafeCell<MaybeUninit<T>>` has no validity invariant, so an
+            // uninitialized array of them is valid.
+            slots: unsafe { MaybeUninit::uninit().assume_init() },
+            next: AtomicPtr::new(ptr::null_mut()),
+        })
+    }
+}
+
+/// An unbounded single-producer single-consumer queue.
+///
+/// Grows by appending fixed-size segment nodes that the consumer reclaims, avoiding the
+/// per-slot sequence-number overhead of the MPMC path.
+pub(crate) struct Unbounded<T> {
+    head_count: AtomicUsize,
+    tail_count: AtomicUsize,
+    head_segment: UnsafeCell<*mut Segment<T>>,
+    tail_segment: UnsafeCell<*mut Segment<T>>,
+    closed: AtomicBool,
+    producer: SingleAccess,
+    consumer: SingleAccess,
+}
+
+unsafe impl<T: Send> Send for Unbounded<T> {}
+unsafe impl<T: Send> Sync for Unbounded<T> {}
+
+impl<T> Unbounded<T> {
+    /// Creates a new unbounded SPSC queue.
+    pub(crate) fn new() -> Unbounded<T> {
+        let segment = Box::into_raw(Segment::new());
+
+        Unbounded {
+            head_count: AtomicUsize::new(0),
+            tail_count: AtomicUsize::new(0),
+            head_segment: UnsafeCell::new(segment),
+            tail_segment: UnsafeCell::new(segment),
+            closed: AtomicBool::new(false),
+            producer: SingleAccess::new(),
+            consumer: SingleAccess::new(),
+        }
+    }
+
+    /// Attempts to push an item into the queue.
+    ///
+    /// Must only be called by the single producer; panics if called concurrently with another
+    /// `push` call.
+    pub(crate) fn push(&self, value: T) -> Result<(), PushError<T>> {
+        let _guard = self.producer.enter();
+
+        if self.closed.load(Ordering::Acquire) {
+            return Err(PushError::Closed(value));
+        }
+
+        let tail = self.tail_count.load(Ordering::Relaxed);
+        let index = tail % SEGMENT_CAP;
+
+        unsafe {
+            let segment = *self.tail_segment.get();
+            (*segment).slots[index].get().write(MaybeUninit::new(value));
+
+            if index + 1 == SEGMENT_CAP {
+                let next = Box::into_raw(Segment::new());
+                (*segment).next.store(next, Ordering::Release);
+                *self.tail_segment.get() = next;
+            }
+        }
+
+        self.tail_count.store(tail.wrapping_add(1), Ordering::Release);
+        Ok(())
+    }
+
+    /// Attempts to pop an item from the queue.
+    ///
+    /// Must only be called by the single consumer; panics if called concurrently with another
+    /// `pop` call.
+    pub(crate) fn pop(&self) -> Result<T, PopError> {
+        let _guard = self.consumer.enter();
+
+        let head = self.head_count.load(Ordering::Relaxed);
+        let tail = self.tail_count.load(Ordering::Acquire);
+
+        if head == tail {
+            return Err(if self.closed.load(Ordering::Acquire) {
+                PopError::Closed
+            } else {
+                PopError::Empty
+            });
+        }
+
+        let index = head % SEGMENT_CAP;
+
+        let value = unsafe {
+            let segment = *self.head_segment.get();
+            let value = (*segment).slots[index].get().read().assume_init();
+
+            if index + 1 == SEGMENT_CAP {
+                let next = (*segment).next.load(Ordering::Acquire);
+                drop(Box::from_raw(segment));
+                *self.head_segment.get() = next;
+            }
+
+            value
+        };
+
+        self.head_count.store(head.wrapping_add(1), Ordering::Release);
+        Ok(value)
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.head_count.load(Ordering::SeqCst) == self.tail_count.load(Ordering::SeqCst)
+    }
+
+    pub(crate) fn is_full(&self) -> bool {
+        false
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        let head = self.head_count.load(Ordering::SeqCst);
+        let tail = self.tail_count.load(Ordering::SeqCst);
+        tail.wrapping_sub(head)
+    }
+
+    pub(crate) fn close(&self) -> bool {
+        !self.closed.swap(true, Ordering::SeqCst)
+    }
+
+    pub(crate) fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
+}
+
+impl<T> Drop for Unbounded<T> {
+    fn drop(&mut self) {
+        let mut head = *self.head_count.get_mut();
+        let tail = *self.tail_count.get_mut();
+        let mut segment = *self.head_segment.get_mut();
+
+        while head != tail {
+            let index = head % SEGMENT_CAP;
+            unsafe {
+                (*segment).slots[index].get_mut().assume_init_drop();
+            }
+            head = head.wrapping_add(1);
+
+            if head.is_multiple_of(SEGMENT_CAP) {
+                let next = unsafe { *(*segment).next.get_mut() };
+                unsafe {
+                    drop(Box::from_raw(segment));
+                }
+                segment = next;
+            }
+        }
+
+        unsafe {
+            drop(Box::from_raw(segment));
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use crate::{PopError, PushError};
+
+/// An unbounded queue.
+pub(crate) struct Unbounded<T> {
+    /// The inner deque, guarded by a lock.
+    ///
+    /// Unbounded queues grow without limit, so there is no Vyukov-style ring to size up front;
+    /// a simple locked deque is the straightforward fit here.
+    queue: Mutex<VecDeque<T>>,
+
+    /// Whether the queue is closed.
+    closed: AtomicBool,
+}
+
+impl<T> Unbounded<T> {
+    /// Creates a new unbounded queue.
+    pub(crate) fn new() -> Unbounded<T> {
+        Unbounded {
+            queue: Mutex::new(VecDeque::new()),
+            closed: AtomicBool::new(false),
+        }
+    }
+
+    /// Attempts to push an item into the queue.
+    pub(crate) fn push(&self, value: T) -> Result<(), PushError<T>> {
+        if self.closed.load(Ordering::SeqCst) {
+            return Err(PushError::Closed(value));
+        }
+
+        let mut queue = self.queue.lock().unwrap();
+
+        if self.closed.load(Ordering::SeqCst) {
+            return Err(PushError::Closed(value));
+        }
+
+        queue.push_back(value);
+        Ok(())
+    }
+
+    /// Attempts to pop an item from the queue.
+    pub(crate) fn pop(&self) -> Result<T, PopError> {
+        let mut queue = self.queue.lock().unwrap();
+
+        match queue.pop_front() {
+            Some(value) => Ok(value),
+            None if self.closed.load(Ordering::SeqCst) => Err(PopError::Closed),
+            None => Err(PopError::Empty),
+        }
+    }
+
+    /// Returns `true` if the queue is empty.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.queue.lock().unwrap().is_empty()
+    }
+
+    /// Returns `true` if the queue is full.
+    ///
+    /// An unbounded queue is never full.
+    pub(crate) fn is_full(&self) -> bool {
+        false
+    }
+
+    /// Returns the number of items in the queue.
+    pub(crate) fn len(&self) -> usize {
+        self.queue.lock().unwrap().len()
+    }
+
+    /// Closes the queue, returning `true` if this call closed it.
+    pub(crate) fn close(&self) -> bool {
+        !self.closed.swap(true, Ordering::SeqCst)
+    }
+
+    /// Returns `true` if the queue is closed.
+    pub(crate) fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
+}
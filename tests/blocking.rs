@@ -0,0 +1,141 @@
+//! Tests for the blocking push/pop API and its interaction with `close()`.
+
+#![cfg(feature = "blocking")]
+
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use concurrent_queue::{ConcurrentQueue, DefaultRecycle, PopError, PushError};
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+/// Regression test for a permanent hang: a losing retry that doesn't re-register after a park
+/// could never be woken again. Four producers contending for four slots reproduces it reliably.
+#[test]
+fn push_pop_blocking_under_contention() {
+    const PRODUCERS: usize = 4;
+    const PER_PRODUCER: usize = 5_000;
+    const TOTAL: usize = PRODUCERS * PER_PRODUCER;
+
+    let q = Arc::new(ConcurrentQueue::bounded(4));
+
+    let producers: Vec<_> = (0..PRODUCERS)
+        .map(|p| {
+            let q = q.clone();
+            thread::spawn(move || {
+                for i in 0..PER_PRODUCER {
+                    q.push_blocking(p * PER_PRODUCER + i).unwrap();
+                }
+            })
+        })
+        .collect();
+
+    let q2 = q.clone();
+    let consumer = thread::spawn(move || {
+        let mut got = Vec::with_capacity(TOTAL);
+        for _ in 0..TOTAL {
+            got.push(q2.pop_blocking().unwrap());
+        }
+        got
+    });
+
+    for p in producers {
+        p.join().unwrap();
+    }
+
+    let mut got = consumer.join().unwrap();
+    got.sort_unstable();
+    assert_eq!(got, (0..TOTAL).collect::<Vec<_>>());
+}
+
+#[test]
+fn close_wakes_producer_parked_on_full_bounded_queue() {
+    let q = Arc::new(ConcurrentQueue::bounded(1));
+    q.push(1).unwrap();
+
+    let q2 = q.clone();
+    let parked = thread::spawn(move || q2.push_blocking(2));
+
+    thread::sleep(ms(200));
+    q.close();
+
+    assert_eq!(parked.join().unwrap(), Err(PushError::Closed(2)));
+}
+
+#[test]
+fn close_wakes_consumer_parked_on_empty_bounded_queue() {
+    let q = Arc::new(ConcurrentQueue::<i32>::bounded(1));
+
+    let q2 = q.clone();
+    let parked = thread::spawn(move || q2.pop_blocking());
+
+    thread::sleep(ms(200));
+    q.close();
+
+    assert_eq!(parked.join().unwrap(), Err(PopError::Closed));
+}
+
+#[test]
+fn close_wakes_producer_parked_on_full_recycled_queue() {
+    let q = Arc::new(ConcurrentQueue::bounded_with_recycle(1, DefaultRecycle));
+    q.push(vec![1]).unwrap();
+
+    let q2 = q.clone();
+    let parked = thread::spawn(move || q2.push_blocking(vec![2]));
+
+    thread::sleep(ms(200));
+    q.close();
+
+    assert_eq!(parked.join().unwrap(), Err(PushError::Closed(vec![2])));
+}
+
+#[test]
+fn close_wakes_consumer_parked_on_empty_recycled_queue() {
+    let q: Arc<ConcurrentQueue<Vec<i32>>> =
+        Arc::new(ConcurrentQueue::bounded_with_recycle(1, DefaultRecycle));
+
+    let q2 = q.clone();
+    let parked = thread::spawn(move || q2.pop_blocking());
+
+    thread::sleep(ms(200));
+    q.close();
+
+    assert_eq!(parked.join().unwrap(), Err(PopError::Closed));
+}
+
+#[test]
+fn push_ref_wakes_consumer_parked_on_empty_recycled_queue() {
+    let q: Arc<ConcurrentQueue<Vec<i32>>> =
+        Arc::new(ConcurrentQueue::bounded_with_recycle(1, DefaultRecycle));
+
+    let q2 = q.clone();
+    let parked = thread::spawn(move || q2.pop_blocking());
+
+    thread::sleep(ms(200));
+    {
+        let mut slot = q.push_ref().unwrap();
+        slot.push(42);
+    }
+
+    assert_eq!(parked.join().unwrap(), Ok(vec![42]));
+}
+
+#[test]
+fn pop_ref_wakes_producer_parked_on_full_recycled_queue() {
+    let q = Arc::new(ConcurrentQueue::bounded_with_recycle(1, DefaultRecycle));
+    q.push(vec![1]).unwrap();
+
+    let q2 = q.clone();
+    let parked = thread::spawn(move || q2.push_blocking(vec![2]));
+
+    thread::sleep(ms(200));
+    {
+        let popped = q.pop_ref().unwrap();
+        assert_eq!(&*popped, &vec![1]);
+    }
+
+    assert_eq!(parked.join().unwrap(), Ok(()));
+}
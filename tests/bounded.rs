@@ -0,0 +1,70 @@
+//! Multi-threaded stress tests for the plain bounded MPMC backend.
+
+use std::sync::Arc;
+use std::thread;
+
+use concurrent_queue::{ConcurrentQueue, PopError, PushError};
+
+/// Spin-pushes `value`, retrying on `Full` until the queue has room.
+fn spin_push<T>(q: &ConcurrentQueue<T>, mut value: T) {
+    loop {
+        match q.push(value) {
+            Ok(()) => return,
+            Err(PushError::Full(v)) => value = v,
+            Err(PushError::Closed(_)) => panic!("queue closed unexpectedly"),
+        }
+        thread::yield_now();
+    }
+}
+
+#[test]
+fn mpmc_no_loss_no_duplication() {
+    const PRODUCERS: usize = 4;
+    const CONSUMERS: usize = 4;
+    const PER_PRODUCER: usize = 2_000;
+    const TOTAL: usize = PRODUCERS * PER_PRODUCER;
+
+    let q = Arc::new(ConcurrentQueue::bounded(16));
+
+    let producers: Vec<_> = (0..PRODUCERS)
+        .map(|p| {
+            let q = q.clone();
+            thread::spawn(move || {
+                for i in 0..PER_PRODUCER {
+                    spin_push(&q, p * PER_PRODUCER + i);
+                }
+            })
+        })
+        .collect();
+
+    let consumers: Vec<_> = (0..CONSUMERS)
+        .map(|_| {
+            let q = q.clone();
+            thread::spawn(move || {
+                let mut got = Vec::new();
+                loop {
+                    match q.pop() {
+                        Ok(v) => got.push(v),
+                        Err(PopError::Closed) => break,
+                        Err(PopError::Empty) => thread::yield_now(),
+                    }
+                }
+                got
+            })
+        })
+        .collect();
+
+    for p in producers {
+        p.join().unwrap();
+    }
+    // Closing only stops *new* pushes; every item already pushed is still drained below.
+    q.close();
+
+    let mut all = Vec::with_capacity(TOTAL);
+    for c in consumers {
+        all.extend(c.join().unwrap());
+    }
+
+    all.sort_unstable();
+    assert_eq!(all, (0..TOTAL).collect::<Vec<_>>());
+}
@@ -0,0 +1,63 @@
+//! Stress test for `close_and_drain()` racing a concurrent consumer: every pushed item must be
+//! accounted for exactly once, whether picked up by the consumer's `pop()` or by the drain.
+
+use std::sync::Arc;
+use std::thread;
+
+use concurrent_queue::{ConcurrentQueue, PopError, PushError};
+
+#[test]
+fn close_and_drain_accounts_for_every_item_under_contention() {
+    const PRODUCERS: usize = 4;
+    const PER_PRODUCER: usize = 2_000;
+    const TOTAL: usize = PRODUCERS * PER_PRODUCER;
+
+    let q = Arc::new(ConcurrentQueue::bounded(16));
+
+    let producers: Vec<_> = (0..PRODUCERS)
+        .map(|p| {
+            let q = q.clone();
+            thread::spawn(move || {
+                for i in 0..PER_PRODUCER {
+                    let mut value = p * PER_PRODUCER + i;
+                    loop {
+                        match q.push(value) {
+                            Ok(()) => break,
+                            Err(PushError::Full(v)) => value = v,
+                            Err(PushError::Closed(_)) => panic!("queue closed unexpectedly"),
+                        }
+                        thread::yield_now();
+                    }
+                }
+            })
+        })
+        .collect();
+
+    // Consumer runs concurrently with the producers above (capacity is far smaller than TOTAL,
+    // so producers rely on it draining slots). Only once every item is pushed do we race a second
+    // drain path, `close_and_drain()`, against this consumer's `pop()`: the two must never hand
+    // out the same item twice, nor leave one behind.
+    let q2 = q.clone();
+    let consumer = thread::spawn(move || {
+        let mut got = Vec::new();
+        loop {
+            match q2.pop() {
+                Ok(v) => got.push(v),
+                Err(PopError::Closed) => break,
+                Err(PopError::Empty) => thread::yield_now(),
+            }
+        }
+        got
+    });
+
+    for p in producers {
+        p.join().unwrap();
+    }
+
+    let leftover: Vec<_> = q.close_and_drain().collect();
+    let popped = consumer.join().unwrap();
+
+    let mut all: Vec<_> = popped.into_iter().chain(leftover).collect();
+    all.sort_unstable();
+    assert_eq!(all, (0..TOTAL).collect::<Vec<_>>());
+}
@@ -0,0 +1,69 @@
+//! Multi-threaded stress tests for the allocation-recycling backend.
+
+use std::sync::Arc;
+use std::thread;
+
+use concurrent_queue::{ConcurrentQueue, DefaultRecycle, PopError, PushError};
+
+fn spin_push(q: &ConcurrentQueue<Vec<usize>>, value: usize) {
+    let mut v = vec![value];
+    loop {
+        match q.push(v) {
+            Ok(()) => return,
+            Err(PushError::Full(back)) => v = back,
+            Err(PushError::Closed(_)) => panic!("queue closed unexpectedly"),
+        }
+        thread::yield_now();
+    }
+}
+
+#[test]
+fn mpmc_no_loss_no_duplication() {
+    const PRODUCERS: usize = 4;
+    const CONSUMERS: usize = 4;
+    const PER_PRODUCER: usize = 2_000;
+    const TOTAL: usize = PRODUCERS * PER_PRODUCER;
+
+    let q = Arc::new(ConcurrentQueue::bounded_with_recycle(16, DefaultRecycle));
+
+    let producers: Vec<_> = (0..PRODUCERS)
+        .map(|p| {
+            let q = q.clone();
+            thread::spawn(move || {
+                for i in 0..PER_PRODUCER {
+                    spin_push(&q, p * PER_PRODUCER + i);
+                }
+            })
+        })
+        .collect();
+
+    let consumers: Vec<_> = (0..CONSUMERS)
+        .map(|_| {
+            let q = q.clone();
+            thread::spawn(move || {
+                let mut got = Vec::new();
+                loop {
+                    match q.pop() {
+                        Ok(v) => got.push(v[0]),
+                        Err(PopError::Closed) => break,
+                        Err(PopError::Empty) => thread::yield_now(),
+                    }
+                }
+                got
+            })
+        })
+        .collect();
+
+    for p in producers {
+        p.join().unwrap();
+    }
+    q.close();
+
+    let mut all = Vec::with_capacity(TOTAL);
+    for c in consumers {
+        all.extend(c.join().unwrap());
+    }
+
+    all.sort_unstable();
+    assert_eq!(all, (0..TOTAL).collect::<Vec<_>>());
+}
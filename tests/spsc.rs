@@ -0,0 +1,66 @@
+//! Single-producer single-consumer stress tests for the spsc fast path.
+
+use std::sync::Arc;
+use std::thread;
+
+use concurrent_queue::{ConcurrentQueue, PopError, PushError};
+
+#[test]
+fn bounded_spsc_single_producer_single_consumer() {
+    const TOTAL: usize = 200_000;
+
+    let q = Arc::new(ConcurrentQueue::bounded_spsc(64));
+    let q2 = q.clone();
+
+    let producer = thread::spawn(move || {
+        for i in 0..TOTAL {
+            let mut v = i;
+            loop {
+                match q2.push(v) {
+                    Ok(()) => break,
+                    Err(PushError::Full(back)) => v = back,
+                    Err(PushError::Closed(_)) => panic!("queue closed unexpectedly"),
+                }
+                thread::yield_now();
+            }
+        }
+    });
+
+    let mut got = Vec::with_capacity(TOTAL);
+    while got.len() < TOTAL {
+        match q.pop() {
+            Ok(v) => got.push(v),
+            Err(PopError::Empty) => thread::yield_now(),
+            Err(PopError::Closed) => panic!("queue closed unexpectedly"),
+        }
+    }
+
+    producer.join().unwrap();
+    assert_eq!(got, (0..TOTAL).collect::<Vec<_>>());
+}
+
+#[test]
+fn unbounded_spsc_single_producer_single_consumer() {
+    const TOTAL: usize = 200_000;
+
+    let q = Arc::new(ConcurrentQueue::spsc());
+    let q2 = q.clone();
+
+    let producer = thread::spawn(move || {
+        for i in 0..TOTAL {
+            q2.push(i).unwrap();
+        }
+    });
+
+    let mut got = Vec::with_capacity(TOTAL);
+    while got.len() < TOTAL {
+        match q.pop() {
+            Ok(v) => got.push(v),
+            Err(PopError::Empty) => thread::yield_now(),
+            Err(PopError::Closed) => panic!("queue closed unexpectedly"),
+        }
+    }
+
+    producer.join().unwrap();
+    assert_eq!(got, (0..TOTAL).collect::<Vec<_>>());
+}